@@ -8,8 +8,37 @@ import { fileURLToPath } from 'url';
 
 const __dirname = dirname(fileURLToPath(import.meta.url));
 const DATA_DIR = join(__dirname, '..', 'data');
-const MAX_SNAPSHOTS = 168; // 7 days of hourly snapshots
+const SNAPSHOTS_PER_DAY = 24; // Hourly collection
+const MAX_SNAPSHOTS = 7 * SNAPSHOTS_PER_DAY; // Default retention: 7 days of hourly snapshots
 const BATCH_SIZE = 100; // Concurrent requests per batch (increased for speed)
+const DELETED_AFTER_ROUNDS = 3; // Consecutive "not found" rounds before a canister is marked deleted
+const TOP_FAILING_PROXIES = 10; // Proxies listed in the round summary log
+
+// Per-project and per-canister retention overrides, in days.
+// A canister override takes precedence over its project's override;
+// everything else falls back to MAX_SNAPSHOTS.
+// Default overrides cover the core NNS system canisters.
+const RETENTION_OVERRIDES = {
+  projects: {
+    'NNS Registry': 90,
+    'NNS Governance': 90,
+    'NNS Root': 90,
+    'NNS Lifeline': 90,
+    'NNS dapp': 90,
+    'NNS Subnet Management': 90,
+    'NNS Subnet Rental': 90,
+    'NNS Node Provider Rewards': 90,
+    'NNS Canister Migration': 90,
+    'ICP Ledger': 90,
+    'ICP Ledger Archive': 90,
+    'ICP Index': 90,
+    'Cycles Minting Canister (CMC)': 90,
+    'SNS-W': 90,
+    'Internet Identity': 90,
+    'Genesis Token': 90,
+  },
+  canisters: {},
+};
 
 // ============================================================================
 // IDL Definitions
@@ -77,8 +106,46 @@ function loadExistingSnapshots() {
   try {
     return JSON.parse(readFileSync(snapshotsPath, 'utf-8'));
   } catch {
-    return { snapshots: [] };
+    return { snapshots: [], not_found: {}, deleted: {}, orphaned: {} };
+  }
+}
+
+// ============================================================================
+// Retention
+// ============================================================================
+
+// Number of snapshots to keep for a canister
+function getRetention(canister) {
+  const days = RETENTION_OVERRIDES.canisters[canister.canister_id]
+    ?? RETENTION_OVERRIDES.projects[canister.project?.[0]];
+  return days !== undefined ? days * SNAPSHOTS_PER_DAY : MAX_SNAPSHOTS;
+}
+
+// Drop balances older than each canister's retention.
+// Snapshots are newest first, so a balance at index i is kept only if the
+// canister's retention is greater than i. Snapshots left empty are removed.
+function pruneSnapshots(snapshots, canisters) {
+  const retention = new Map(canisters.map(c => [c.canister_id, getRetention(c)]));
+  const maxRetention = Math.max(MAX_SNAPSHOTS, ...retention.values());
+
+  const pruned = [];
+  for (let i = 0; i < Math.min(snapshots.length, maxRetention); i++) {
+    const snapshot = snapshots[i];
+    const balances = {};
+    for (const [id, balance] of Object.entries(snapshot.balances)) {
+      if ((retention.get(id) ?? MAX_SNAPSHOTS) > i) {
+        balances[id] = balance;
+      }
+    }
+    if (Object.keys(balances).length > 0) {
+      const kept = { ...snapshot, balances };
+      if (snapshot.stale) {
+        kept.stale = snapshot.stale.filter(id => id in balances);
+      }
+      pruned.push(kept);
+    }
   }
+  return pruned;
 }
 
 // ============================================================================
@@ -95,6 +162,12 @@ function withTimeout(promise, ms, errorMsg) {
   ]);
 }
 
+// The proxy reports the target canister itself as missing (deleted or never existed)
+function isCanisterNotFound(error, canisterId) {
+  return /not found/i.test(error.message) && error.message.includes(canisterId);
+}
+
+// Returns { balance, notFound }; balance is null if the query failed
 async function queryBlackhole(agent, proxyId, canisterId) {
   try {
     const actor = Actor.createActor(blackholeIdl, {
@@ -106,13 +179,15 @@ async function queryBlackhole(agent, proxyId, canisterId) {
       30000,  // 30 second timeout per query
       `Timeout querying ${canisterId}`
     );
-    return result.cycles.toString();
+    return { balance: result.cycles.toString(), notFound: false };
   } catch (e) {
     console.error(`  Failed to query ${canisterId} via ${proxyId}: ${e.message}`);
-    return null;
+    return { balance: null, notFound: isCanisterNotFound(e, canisterId) };
   }
 }
 
+// Returns { balances, listed } or null if the query failed.
+// `listed` holds every canister the root reports, with or without a status.
 async function querySnsRoot(agent, snsRootId) {
   try {
     const actor = Actor.createActor(snsRootIdl, {
@@ -127,6 +202,7 @@ async function querySnsRoot(agent, snsRootId) {
 
     // Extract all canisters and their cycles
     const balances = new Map();
+    const listed = new Set();
 
     const allCanisters = [
       result.root,
@@ -139,17 +215,18 @@ async function querySnsRoot(agent, snsRootId) {
     ];
 
     for (const summary of allCanisters) {
-      if (summary && summary.canister_id?.[0] && summary.status?.[0]) {
-        const id = summary.canister_id[0].toText();
-        const cycles = summary.status[0].cycles.toString();
-        balances.set(id, cycles);
+      if (!summary?.canister_id?.[0]) continue;
+      const id = summary.canister_id[0].toText();
+      listed.add(id);
+      if (summary.status?.[0]) {
+        balances.set(id, summary.status[0].cycles.toString());
       }
     }
 
-    return balances;
+    return { balances, listed };
   } catch (e) {
     console.error(`  Failed to query SNS root ${snsRootId}: ${e.message}`);
-    return new Map();
+    return null;
   }
 }
 
@@ -159,6 +236,9 @@ async function querySnsRoot(agent, snsRootId) {
 
 async function collectBalances(agent, canisters) {
   const results = new Map();
+  const notFound = new Set();
+  const droppedFromSns = new Set();
+  const listedBySns = new Set();
 
   // Separate canisters by proxy type
   const blackholeCanisters = canisters.filter(c => c.proxy_type?.Blackhole !== undefined);
@@ -177,11 +257,24 @@ async function collectBalances(agent, canisters) {
   for (let i = 0; i < snsRoots.length; i++) {
     const snsRootId = snsRoots[i];
     process.stdout.write(`  SNS ${i + 1}/${snsRoots.length} (${snsRootId.slice(0, 5)}...)...`);
-    const snsBalances = await querySnsRoot(agent, snsRootId);
-    for (const [id, balance] of snsBalances) {
-      results.set(id, balance);
+    const snsResult = await querySnsRoot(agent, snsRootId);
+    if (snsResult) {
+      for (const [id, balance] of snsResult.balances) {
+        results.set(id, balance);
+      }
+      for (const id of snsResult.listed) {
+        listedBySns.add(id);
+      }
+      // Tracked canisters this root no longer lists
+      for (const c of snsCanisters) {
+        if (c.proxy_id === snsRootId && !snsResult.listed.has(c.canister_id)) {
+          droppedFromSns.add(c.canister_id);
+        }
+      }
+      console.log(` ${snsResult.balances.size} canisters`);
+    } else {
+      console.log(' failed');
     }
-    console.log(` ${snsBalances.size} canisters`);
     // Small delay between SNS queries
     await new Promise(r => setTimeout(r, 100));
   }
@@ -201,16 +294,18 @@ async function collectBalances(agent, canisters) {
 
     const batchResults = await Promise.all(
       batch.map(async (c) => {
-        const balance = await queryBlackhole(agent, c.proxy_id, c.canister_id);
-        return { id: c.canister_id, balance };
+        const result = await queryBlackhole(agent, c.proxy_id, c.canister_id);
+        return { id: c.canister_id, ...result };
       })
     );
 
     let successCount = 0;
-    for (const { id, balance } of batchResults) {
+    for (const { id, balance, notFound: missing } of batchResults) {
       if (balance !== null) {
         results.set(id, balance);
         successCount++;
+      } else if (missing) {
+        notFound.add(id);
       }
     }
     console.log(` ${successCount}/${batch.length} succeeded`);
@@ -219,7 +314,118 @@ async function collectBalances(agent, canisters) {
     await new Promise(r => setTimeout(r, 200));
   }
 
-  return results;
+  return { balances: results, notFound, droppedFromSns, listedBySns };
+}
+
+// Per-proxy-type and per-proxy success/failure counts for this round, plus
+// the canisters that got no fresh balance. Persisted as `last_round`.
+function summarizeRound(timestamp, canisters, balances) {
+  const byType = {};
+  const byProxy = {};
+  const failed = [];
+  for (const c of canisters) {
+    const type = Object.keys(c.proxy_type)[0];
+    const ok = balances.has(c.canister_id);
+    byType[type] ??= { succeeded: 0, failed: 0 };
+    byProxy[c.proxy_id] ??= { proxy_type: type, succeeded: 0, failed: 0 };
+    if (ok) {
+      byType[type].succeeded++;
+      byProxy[c.proxy_id].succeeded++;
+    } else {
+      byType[type].failed++;
+      byProxy[c.proxy_id].failed++;
+      failed.push({ canister_id: c.canister_id, proxy_id: c.proxy_id });
+    }
+  }
+  return { timestamp, by_proxy_type: byType, by_proxy: byProxy, failed };
+}
+
+// Failed queries are already logged as they happen, so this prints only
+// counts and the proxies with the most failures
+function logRoundSummary(summary) {
+  const line = (key, stats) =>
+    `  ${key}: ${stats.succeeded}/${stats.succeeded + stats.failed} succeeded`;
+
+  console.log(`\nResults by proxy type:`);
+  for (const [type, stats] of Object.entries(summary.by_proxy_type)) {
+    console.log(line(type, stats));
+  }
+
+  console.log(`\nFailed canisters: ${summary.failed.length}`);
+  const failing = Object.entries(summary.by_proxy)
+    .filter(([, stats]) => stats.failed > 0)
+    .sort((a, b) => b[1].failed - a[1].failed);
+  if (failing.length > 0) {
+    console.log(`Top failing proxies (${Math.min(failing.length, TOP_FAILING_PROXIES)} of ${failing.length}):`);
+    for (const [proxy, stats] of failing.slice(0, TOP_FAILING_PROXIES)) {
+      console.log(line(`${stats.proxy_type} ${proxy}`, stats));
+    }
+  }
+}
+
+// Mark canisters their SNS root stopped listing as orphaned, and clear the
+// mark once the root lists them again (with or without a status).
+function updateOrphaned(existing, droppedFromSns, listedBySns, timestamp) {
+  const orphaned = { ...existing.orphaned };
+  for (const id of droppedFromSns) {
+    if (!orphaned[id]) {
+      orphaned[id] = timestamp;
+      console.log(`  Marked ${id} as orphaned (no longer listed by its SNS root)`);
+    }
+  }
+  for (const id of Object.keys(orphaned)) {
+    if (listedBySns.has(id)) {
+      delete orphaned[id];
+      console.log(`  ${id} is listed by its SNS root again`);
+    }
+  }
+  return orphaned;
+}
+
+// Count consecutive "not found" rounds and mark canisters deleted once the
+// streak reaches DELETED_AFTER_ROUNDS. The "not found" match is on error text,
+// so a deleted canister that reports a balance again (see isReprobeRound) has
+// its mark cleared.
+function updateDeleted(existing, notFound, balances, timestamp) {
+  const deleted = { ...existing.deleted };
+
+  const notFoundRounds = {};
+  for (const id of notFound) {
+    if (deleted[id]) continue;
+    notFoundRounds[id] = (existing.not_found?.[id] ?? 0) + 1;
+  }
+
+  for (const [id, rounds] of Object.entries(notFoundRounds)) {
+    if (rounds >= DELETED_AFTER_ROUNDS) {
+      deleted[id] = timestamp;
+      delete notFoundRounds[id];
+      console.log(`  Marked ${id} as deleted`);
+    }
+  }
+
+  for (const id of Object.keys(deleted)) {
+    if (balances.has(id)) {
+      delete deleted[id];
+      console.log(`  ${id} responded again, cleared deleted mark`);
+    }
+  }
+
+  return { notFoundRounds, deleted };
+}
+
+// Deleted canisters are re-probed in the first round of each UTC day
+function isReprobeRound(existing, now) {
+  const lastRound = existing.snapshots[0]?.timestamp;
+  if (!lastRound) return true;
+  const day = ts => new Date(ts).toISOString().slice(0, 10);
+  return day(lastRound) !== day(now);
+}
+
+// Drop marks for canisters no longer in the registry (removed from
+// canisters_backup.json or set to valid: false)
+function keepRegistered(marks, registry) {
+  const ids = new Set(registry.map(c => c.canister_id));
+  return Object.fromEntries(Object.entries(marks).filter(([id]) => ids.has(id)));
 }
 
 async function main() {
@@ -232,48 +438,80 @@ async function main() {
   const agent = new HttpAgent({ host: 'https://icp-api.io' });
 
   // Load canister registry
-  const canisters = loadCanisters();
-  console.log(`\nLoaded ${canisters.length} canisters from registry`);
+  const registry = loadCanisters();
+  console.log(`\nLoaded ${registry.length} canisters from registry`);
 
   // Load existing snapshots
   const existing = loadExistingSnapshots();
   console.log(`Existing snapshots: ${existing.snapshots.length}`);
 
+  // Skip canisters already known to be deleted, except in the daily re-probe round
+  const knownDeleted = registry.filter(c => existing.deleted?.[c.canister_id]).length;
+  const reprobe = isReprobeRound(existing, Date.now());
+  const canisters = reprobe
+    ? registry
+    : registry.filter(c => !existing.deleted?.[c.canister_id]);
+  console.log(reprobe
+    ? `Re-probing ${knownDeleted} deleted canisters`
+    : `Deleted canisters skipped: ${knownDeleted}`);
+
   // Get last known balances (for fallback on failed queries)
   const lastKnownBalances = existing.snapshots[0]?.balances || {};
 
   // Collect current balances
-  const currentBalances = await collectBalances(agent, canisters);
+  const { balances: currentBalances, notFound, droppedFromSns, listedBySns } = await collectBalances(agent, canisters);
+  const timestamp = Date.now();
+  const { notFoundRounds, deleted } = updateDeleted(existing, notFound, currentBalances, timestamp);
+  const orphaned = updateOrphaned(existing, droppedFromSns, listedBySns, timestamp);
+  // Deleted and orphaned canisters are expected to fail, so they are left out
+  const lastRound = summarizeRound(
+    timestamp,
+    canisters.filter(c => !deleted[c.canister_id] && !orphaned[c.canister_id]),
+    currentBalances,
+  );
+  logRoundSummary(lastRound);
 
   // Merge with last known values for failed queries
   const finalBalances = {};
+  const stale = [];
   for (const c of canisters) {
     const current = currentBalances.get(c.canister_id);
     if (current !== undefined) {
       finalBalances[c.canister_id] = current;
-    } else if (lastKnownBalances[c.canister_id]) {
+    } else if (!deleted[c.canister_id] && !orphaned[c.canister_id] && lastKnownBalances[c.canister_id]) {
       // Keep last known value if query failed
       finalBalances[c.canister_id] = lastKnownBalances[c.canister_id];
+      stale.push(c.canister_id);
     }
   }
 
   console.log(`\nFinal balances: ${Object.keys(finalBalances).length} canisters`);
   console.log(`  - Fresh queries: ${currentBalances.size}`);
   console.log(`  - From last known: ${Object.keys(finalBalances).length - currentBalances.size}`);
+  console.log(`  - Not found this round: ${notFound.size}`);
+  console.log(`  - Orphaned SNS canisters: ${Object.keys(orphaned).length}`);
 
   // Create new snapshot
+  // `stale` lists the balances carried forward instead of freshly queried
   const newSnapshot = {
-    timestamp: Date.now(),
+    timestamp,
     balances: finalBalances,
+    stale,
   };
 
-  // Prepend to snapshots array, keep only MAX_SNAPSHOTS
-  const newSnapshots = [newSnapshot, ...existing.snapshots].slice(0, MAX_SNAPSHOTS);
+  // Prepend to snapshots array, prune to each canister's retention
+  const newSnapshots = pruneSnapshots([newSnapshot, ...existing.snapshots], registry);
 
   // Write output
   mkdirSync(join(DATA_DIR, 'live'), { recursive: true });
   const outputPath = join(DATA_DIR, 'live', 'snapshots.json');
-  writeFileSync(outputPath, JSON.stringify({ snapshots: newSnapshots }, null, 2));
+  writeFileSync(outputPath, JSON.stringify({
+    snapshots: newSnapshots,
+    not_found: keepRegistered(notFoundRounds, registry),
+    deleted: keepRegistered(deleted, registry),
+    orphaned: keepRegistered(orphaned, registry),
+    last_round: lastRound,
+  }, null, 2));
 
   console.log(`\nWrote ${newSnapshots.length} snapshots to ${outputPath}`);
   console.log('='.repeat(60));
@@ -4,7 +4,10 @@
 import {
   calculateBurnRate,
   aggregateProjectRate,
+  analyzeIntervals,
   getIntervalsForChart,
+  getWindowPoints,
+  downsamplePoints,
   type BurnRateData,
   type ProjectRateData,
   type IntervalData,
@@ -16,10 +19,33 @@ const GITHUB_RAW_BASE = 'https://raw.githubusercontent.com/AlexandriaDAO/cyclesc
 export interface Snapshot {
   timestamp: number;
   balances: Record<string, string>;
+  // Canisters whose balance was carried forward from the previous round
+  // (missing on rounds collected before this was recorded)
+  stale?: string[];
 }
 
 export interface SnapshotsData {
   snapshots: Snapshot[];
+  // Consecutive rounds in which a proxy reported the canister as not found
+  not_found?: Record<string, number>;
+  // Canisters confirmed deleted, with detection timestamp (ms)
+  deleted?: Record<string, number>;
+  // Canisters their SNS root no longer lists, with detection timestamp (ms)
+  orphaned?: Record<string, number>;
+  // Success/failure breakdown of the latest collection round
+  last_round?: RoundSummary;
+}
+
+export interface ProxyStats {
+  succeeded: number;
+  failed: number;
+}
+
+export interface RoundSummary {
+  timestamp: number;
+  by_proxy_type: Record<string, ProxyStats>;
+  by_proxy: Record<string, ProxyStats & { proxy_type: string }>;
+  failed: Array<{ canister_id: string; proxy_id: string }>;
 }
 
 export interface CanisterRegistry {
@@ -38,6 +64,12 @@ export interface ProjectMeta {
 // Re-export types for external use
 export type { BurnRateData, ProjectRateData, IntervalData };
 
+// Caveat on a canister's figures, most severe first:
+// stale     - latest balance was carried forward, not freshly queried
+// sparse    - fresh balance in under 80% of rounds over the last 7 days
+// estimated - burn for a top-up interval was inferred rather than measured
+export type DataQuality = 'ok' | 'stale' | 'sparse' | 'estimated';
+
 // Canister entry with burn rates
 export interface CanisterEntry {
   canister_id: string;
@@ -47,6 +79,7 @@ export interface CanisterEntry {
   recent_rate: BurnRateData | null;
   short_term_rate: BurnRateData | null;
   long_term_rate: BurnRateData | null;
+  quality: DataQuality;
 }
 
 // Project entry with aggregated rates
@@ -63,6 +96,14 @@ export interface ProjectEntry {
 // Global stats
 export interface Stats {
   canister_count: bigint;
+  // Canisters freshly queried in the latest round (null if not recorded)
+  fresh_count: number | null;
+  total_balance: bigint;
+  // Cycles burned over the last 1h/24h/7d, from the recent, short-term and
+  // long-term rates; cycle-transfer canisters are excluded
+  burned_1h: bigint;
+  burned_24h: bigint;
+  burned_7d: bigint;
   snapshot_count: number;
   last_updated: Date | null;
 }
@@ -81,6 +122,9 @@ let cachedData: {
 const HOUR_MS = 60 * 60 * 1000;
 const DAY_MS = 24 * HOUR_MS;
 
+// Below this 7d coverage a canister's figures are flagged as sparse
+const LOW_COVERAGE_RATIO = 0.8;
+
 export async function loadData(): Promise<{
   entries: CanisterEntry[];
   projectEntries: ProjectEntry[];
@@ -130,6 +174,12 @@ export async function loadData(): Promise<{
     longTermRates: (BurnRateData | null)[];
   }>();
 
+  // Network-wide totals
+  let totalBalance = 0n;
+  let recentRateSum = 0n;
+  let shortTermRateSum = 0n;
+  let longTermRateSum = 0n;
+
   for (const canister of canistersRegistry) {
     const balanceStr = currentSnapshot.balances[canister.canister_id];
     if (!balanceStr) continue;
@@ -149,9 +199,17 @@ export async function loadData(): Promise<{
       recent_rate: recentRate,
       short_term_rate: shortTermRate,
       long_term_rate: longTermRate,
+      quality: getDataQuality(snapshots, now, canister.canister_id, shortTermRate),
     };
     entries.push(entry);
 
+    totalBalance += balance;
+    if (canister.valid) {
+      recentRateSum += recentRate?.rate ?? 0n;
+      shortTermRateSum += shortTermRate?.rate ?? 0n;
+      longTermRateSum += longTermRate?.rate ?? 0n;
+    }
+
     // Aggregate by project
     const projectName = canister.project?.[0];
     if (projectName) {
@@ -201,6 +259,14 @@ export async function loadData(): Promise<{
 
   const stats: Stats = {
     canister_count: BigInt(entries.length),
+    fresh_count: snapshots[0]?.stale
+      ? entries.filter(e => !isStale(snapshots[0], e.canister_id)).length
+      : null,
+    total_balance: totalBalance,
+    // Rates are cycles per hour
+    burned_1h: recentRateSum,
+    burned_24h: shortTermRateSum * 24n,
+    burned_7d: longTermRateSum * 7n * 24n,
     snapshot_count: snapshots.length,
     last_updated: snapshots[0] ? new Date(snapshots[0].timestamp) : null,
   };
@@ -231,6 +297,68 @@ export async function getProjectCanisters(projectName: string): Promise<Canister
   return cachedData.entries.filter(e => e.project?.[0] === projectName);
 }
 
+// Per-snapshot lookup of carried-forward ids, built on first use
+const staleSets = new WeakMap<Snapshot, Set<string>>();
+
+function isStale(snapshot: Snapshot, canisterId: string): boolean {
+  let stale = staleSets.get(snapshot);
+  if (!stale) {
+    stale = new Set(snapshot.stale ?? []);
+    staleSets.set(snapshot, stale);
+  }
+  return stale.has(canisterId);
+}
+
+function getDataQuality(
+  snapshots: Snapshot[],
+  now: number,
+  canisterId: string,
+  shortTermRate: BurnRateData | null
+): DataQuality {
+  if (snapshots[0] && isStale(snapshots[0], canisterId)) return 'stale';
+  const coverage = calculateCoverage(snapshots, 7 * DAY_MS, now, canisterId);
+  if (coverage && coverage.ratio < LOW_COVERAGE_RATIO) return 'sparse';
+  if (shortTermRate?.hasInferredData) return 'estimated';
+  return 'ok';
+}
+
+// Share of rounds in which a canister reported a fresh balance
+export interface CoverageData {
+  fresh: number;   // rounds with a freshly queried balance
+  rounds: number;  // rounds collected since the canister's oldest retained balance
+  ratio: number;   // fresh / rounds
+}
+
+// Only rounds that record staleness are counted. The window starts at the
+// canister's oldest retained balance, so pruned history doesn't count as failures.
+function calculateCoverage(
+  snapshots: Snapshot[],
+  windowMs: number,
+  now: number,
+  canisterId: string
+): CoverageData | null {
+  const cutoff = now - windowMs;
+  let start: number | null = null;
+  for (const snapshot of snapshots) {
+    // Snapshots are newest first
+    if (snapshot.timestamp < cutoff) break;
+    if (snapshot.balances[canisterId]) start = snapshot.timestamp;
+  }
+  if (start === null) return null;
+
+  let fresh = 0;
+  let rounds = 0;
+  for (const snapshot of snapshots) {
+    if (snapshot.timestamp < start) break;
+    if (!snapshot.stale) continue;
+    rounds++;
+    if (snapshot.balances[canisterId] && !isStale(snapshot, canisterId)) fresh++;
+  }
+
+  if (rounds === 0) return null;
+  return { fresh, rounds, ratio: fresh / rounds };
+}
+
 // Canister detail for the modal
 export interface CanisterDetail {
   project: string[] | null;
@@ -238,7 +366,10 @@ export interface CanisterDetail {
   recent_rate: BurnRateData | null;
   short_term_rate: BurnRateData | null;
   long_term_rate: BurnRateData | null;
-  // Raw snapshots for chart
+  coverage_7d: CoverageData | null;
+  // Only for canisters whose history reaches back past 7 days
+  coverage_30d: CoverageData | null;
+  // Raw snapshots for chart (full retained history, newest first)
   snapshots: Array<{ timestamp: bigint; cycles: bigint }>;
 }
 
@@ -266,9 +397,11 @@ export async function getCanisterDetail(canisterId: string): Promise<CanisterDet
 
   // Build snapshots array for chart
   const snapshotHistory: Array<{ timestamp: bigint; cycles: bigint }> = [];
+  let oldestTimestamp = now;
   for (const snapshot of snapshots) {
     const cyclesStr = snapshot.balances[canisterId];
     if (cyclesStr) {
+      oldestTimestamp = snapshot.timestamp;
       snapshotHistory.push({
         timestamp: BigInt(snapshot.timestamp) * 1_000_000n,
         cycles: BigInt(cyclesStr),
@@ -276,27 +409,154 @@ export async function getCanisterDetail(canisterId: string): Promise<CanisterDet
     }
   }
 
+  // Only canisters with a retention override keep balances past 7 days
+  const hasLongHistory = oldestTimestamp < now - 7 * DAY_MS;
+
   return {
     project: canisterRegistry.project,
     current_balance: currentBalance,
     recent_rate: recentRate,
     short_term_rate: shortTermRate,
     long_term_rate: longTermRate,
+    coverage_7d: calculateCoverage(snapshots, 7 * DAY_MS, now, canisterId),
+    coverage_30d: hasLongHistory ? calculateCoverage(snapshots, 30 * DAY_MS, now, canisterId) : null,
     snapshots: snapshotHistory,
   };
 }
 
+// Canister flagged by the collector (deleted or orphaned), newest first
+export interface FlaggedCanister {
+  canister_id: string;
+  project: string[] | null;
+  since: Date;
+}
+
+async function getFlaggedCanisters(
+  pick: (data: SnapshotsData) => Record<string, number> | undefined
+): Promise<FlaggedCanister[]> {
+  if (!cachedData) {
+    await loadData();
+  }
+  if (!cachedData) return [];
+
+  const flags = pick(cachedData.snapshots) ?? {};
+  const projectById = new Map(cachedData.canisters.map(c => [c.canister_id, c.project]));
+
+  return Object.entries(flags)
+    .map(([canisterId, timestamp]) => ({
+      canister_id: canisterId,
+      project: projectById.get(canisterId) ?? null,
+      since: new Date(timestamp),
+    }))
+    .sort((a, b) => b.since.getTime() - a.since.getTime());
+}
+
+export function getDeletedCanisters(): Promise<FlaggedCanister[]> {
+  return getFlaggedCanisters(data => data.deleted);
+}
+
+export function getOrphanedCanisters(): Promise<FlaggedCanister[]> {
+  return getFlaggedCanisters(data => data.orphaned);
+}
+
+// Balance change of one canister over a window
+export interface MoverEntry {
+  canister_id: string;
+  project: string[] | null;
+  valid: boolean;
+  start_balance: bigint;
+  end_balance: bigint;
+  change: bigint;          // end - start; negative = burned
+  percent_change: number;  // change relative to start_balance
+}
+
+export interface BiggestMovers {
+  down: { absolute: MoverEntry[]; percent: MoverEntry[] };
+  up: { absolute: MoverEntry[]; percent: MoverEntry[] };
+}
+
+// Canisters with the largest balance changes over the window, both down
+// (heavy burners) and up (big top-ups), by absolute and percentage change
+export async function getBiggestMovers(
+  windowMs: number,
+  limit: number,
+  includeCycleTransfers: boolean = false
+): Promise<BiggestMovers> {
+  const movers: BiggestMovers = {
+    down: { absolute: [], percent: [] },
+    up: { absolute: [], percent: [] },
+  };
+
+  if (!cachedData) {
+    await loadData();
+  }
+  if (!cachedData) return movers;
+
+  const snapshots = cachedData.snapshots.snapshots;
+  const now = snapshots[0]?.timestamp ?? Date.now();
+
+  const changes: MoverEntry[] = [];
+  for (const canister of cachedData.canisters) {
+    if (!includeCycleTransfers && !canister.valid) continue;
+
+    // Points are newest first
+    const points = getWindowPoints(snapshots, windowMs, now, canister.canister_id);
+    if (points.length < 2) continue;
+
+    const start = points[points.length - 1].v;
+    const end = points[0].v;
+    if (end === start) continue;
+
+    changes.push({
+      canister_id: canister.canister_id,
+      project: canister.project,
+      valid: canister.valid,
+      start_balance: BigInt(Math.round(start)),
+      end_balance: BigInt(Math.round(end)),
+      change: BigInt(Math.round(end - start)),
+      percent_change: start > 0 ? ((end - start) / start) * 100 : Infinity,
+    });
+  }
+
+  const byChange = (a: MoverEntry, b: MoverEntry) => (a.change < b.change ? -1 : a.change > b.change ? 1 : 0);
+  const byPercent = (a: MoverEntry, b: MoverEntry) => a.percent_change - b.percent_change;
+  const down = changes.filter(c => c.change < 0n);
+  const up = changes.filter(c => c.change > 0n);
+  // Percentage is undefined for canisters that started at zero
+  const finite = (c: MoverEntry) => Number.isFinite(c.percent_change);
+
+  movers.down.absolute = [...down].sort(byChange).slice(0, limit);
+  movers.down.percent = down.filter(finite).sort(byPercent).slice(0, limit);
+  movers.up.absolute = [...up].sort(byChange).reverse().slice(0, limit);
+  movers.up.percent = up.filter(finite).sort(byPercent).reverse().slice(0, limit);
+
+  return movers;
+}
+
 // Get raw snapshots for chart interval analysis
 export function getRawSnapshots(): Snapshot[] {
   return cachedData?.snapshots.snapshots ?? [];
 }
 
 // Get interval data for chart visualization
-export function getChartIntervals(canisterId: string, windowMs: number): IntervalData[] {
+// Balance history averaged into at most maxPoints buckets, newest first
+export function getHistoryDownsampled(
+  canisterId: string,
+  maxPoints: number,
+  windowMs: number = Infinity
+): Array<{ t: number; v: number }> {
   if (!cachedData) return [];
   const snapshots = cachedData.snapshots.snapshots;
   const now = snapshots[0]?.timestamp ?? Date.now();
-  return getIntervalsForChart(snapshots, windowMs, now, canisterId);
+  return downsamplePoints(getWindowPoints(snapshots, windowMs, now, canisterId), maxPoints);
+}
+
+export function getChartIntervals(
+  canisterId: string,
+  windowMs: number,
+  maxPoints: number = Infinity
+): IntervalData[] {
+  return analyzeIntervals(getHistoryDownsampled(canisterId, maxPoints, windowMs));
 }
 
 // Get sparkline intervals for a project (aggregated from all canisters)
@@ -78,22 +78,20 @@ function safeBalanceToNumber(balanceStr: string): number {
 const MS_PER_HOUR = 3600000;
 
 /**
- * Calculate burn rate from snapshots within a time window.
- * Uses one universal algorithm: average burn rate from actual burn intervals,
- * with inferred values for top-up intervals.
+ * Collect one canister's balance points within a time window, newest first.
  */
-export function calculateBurnRate(
+export function getWindowPoints(
   snapshots: Array<{ timestamp: number; balances: Record<string, string> }>,
   windowMs: number,
   now: number,
   canisterId: string
-): BurnRateData | null {
-  // Collect points for this canister within the window
+): Array<{ t: number; v: number }> {
   const cutoff = now - windowMs;
   const points: Array<{ t: number; v: number }> = [];
 
   for (const snapshot of snapshots) {
-    if (snapshot.timestamp < cutoff) continue;
+    // Snapshots are newest first, so everything after this is older too
+    if (snapshot.timestamp < cutoff) break;
     const balanceStr = snapshot.balances[canisterId];
     if (!balanceStr) continue;
     points.push({
@@ -102,6 +100,50 @@ export function calculateBurnRate(
     });
   }
 
+  return points;
+}
+
+/**
+ * Average points into at most maxPoints equal-time buckets, newest first.
+ * Keeps charts light however long the retained history is.
+ */
+export function downsamplePoints(
+  points: Array<{ t: number; v: number }>,
+  maxPoints: number
+): Array<{ t: number; v: number }> {
+  if (points.length <= maxPoints) return points;
+
+  const sorted = [...points].sort((a, b) => b.t - a.t);
+  const newest = sorted[0].t;
+  const bucketMs = (newest - sorted[sorted.length - 1].t) / maxPoints;
+  if (bucketMs <= 0) return sorted.slice(0, maxPoints);
+
+  const buckets: Array<{ t: number; v: number; n: number }> = [];
+  for (const p of sorted) {
+    const i = Math.min(maxPoints - 1, Math.floor((newest - p.t) / bucketMs));
+    if (!buckets[i]) buckets[i] = { t: 0, v: 0, n: 0 };
+    buckets[i].t += p.t;
+    buckets[i].v += p.v;
+    buckets[i].n++;
+  }
+
+  return buckets.filter(b => b).map(b => ({ t: b.t / b.n, v: b.v / b.n }));
+}
+
+/**
+ * Calculate burn rate from snapshots within a time window.
+ * Uses one universal algorithm: average burn rate from actual burn intervals,
+ * with inferred values for top-up intervals.
+ */
+export function calculateBurnRate(
+  snapshots: Array<{ timestamp: number; balances: Record<string, string> }>,
+  windowMs: number,
+  now: number,
+  canisterId: string
+): BurnRateData | null {
+  // Collect points for this canister within the window
+  const points = getWindowPoints(snapshots, windowMs, now, canisterId);
+
   if (points.length < 2) return null;
 
   const intervals = analyzeIntervals(points);
@@ -196,18 +238,5 @@ export function getIntervalsForChart(
   now: number,
   canisterId: string
 ): IntervalData[] {
-  const cutoff = now - windowMs;
-  const points: Array<{ t: number; v: number }> = [];
-
-  for (const snapshot of snapshots) {
-    if (snapshot.timestamp < cutoff) continue;
-    const balanceStr = snapshot.balances[canisterId];
-    if (!balanceStr) continue;
-    points.push({
-      t: snapshot.timestamp,
-      v: safeBalanceToNumber(balanceStr),
-    });
-  }
-
-  return analyzeIntervals(points);
+  return analyzeIntervals(getWindowPoints(snapshots, windowMs, now, canisterId));
 }